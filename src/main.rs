@@ -4,14 +4,21 @@ extern crate exit_code;
 
 use std::env;
 use std::io;
+use std::path::Path;
 
 use glium::{Surface, DisplayBuild};
-use glium::glutin::Event;
+use glium::glutin::{Event, ElementState, MouseScrollDelta, VirtualKeyCode};
 
 use ruff::Farbfeld;
 use ruff::error as rufferr;
 
 #[macro_use] mod macros;
+mod view;
+
+use view::View;
+
+/// Pixels of touchpad scrolling needed for one zoom step.
+const SCROLL_STEP: f32 = 50.0;
 
 #[derive(Copy, Clone)]
 struct Vertex {
@@ -21,10 +28,11 @@ struct Vertex {
 implement_vertex!(Vertex, position, tex_coords);
 
 fn main() {
-    let img = load_img();
+    let imgs = load_imgs();
+    let mut current = 0;
 
     let display = glium::glutin::WindowBuilder::new()
-        .with_title("Farbfeld Viewer")
+        .with_title(title(&imgs, current))
         .with_vsync()
         .build_glium()
         .unwrap_or_else(|err| exit(exit_code::SERVICE_UNAVAILABLE,
@@ -34,10 +42,11 @@ fn main() {
         in vec2 position;
         in vec2 tex_coords;
         out vec2 uv;
+        uniform mat4 matrix;
 
         void main() {
             uv = tex_coords;
-            gl_Position = vec4(position, 0.0, 1.0);
+            gl_Position = matrix * vec4(position, 0.0, 1.0);
         }"#, r#"
         #version 140
         in vec2 uv;
@@ -61,63 +70,161 @@ fn main() {
                                           index_data)
         .unwrap_or_else(|err| exit(exit_code::FAILURE,
                                    format!("Failed to load index data for rendering! {}", err)));
-    let dimensions = (*img.width(), *img.height());
+    let mut texture = load_texture(&display, &imgs[current].1);
+    let mut view = View::new(dimensions(&imgs[current].1), display.get_framebuffer_dimensions());
+    let mut scrolled = 0.0;
+
+    loop {
+        {
+            let uniform = uniform!(tex: &texture, matrix: view.matrix());
+            let mut target = display.draw();
+            target.clear_color(0.0, 0.0, 0.0, 0.0);
+            target.draw(&vertices, &indices, &program, &uniform, &Default::default())
+                .unwrap_or_else(|err| exit(exit_code::FAILURE,
+                                           format!("Failed to draw image! {}", err)));
+            target.finish().unwrap_or_else(|err| exit(exit_code::FAILURE,
+                                                      format!("Failed to draw image! {}", err)));
+        }
+
+        let mut next = None;
+        for event in display.poll_events() {
+            match event {
+                Event::Closed => return,
+                Event::Resized(width, height) => view.resize((width, height)),
+                Event::MouseWheel(MouseScrollDelta::LineDelta(_, y), _) => {
+                    if y > 0.0 {
+                        view.zoom_in();
+                    } else if y < 0.0 {
+                        view.zoom_out();
+                    }
+                },
+                //Touchpads send many small pixel deltas per gesture, so zoom once per SCROLL_STEP
+                Event::MouseWheel(MouseScrollDelta::PixelDelta(_, y), _) => {
+                    scrolled += y;
+                    while scrolled >= SCROLL_STEP {
+                        view.zoom_in();
+                        scrolled -= SCROLL_STEP;
+                    }
+                    while scrolled <= -SCROLL_STEP {
+                        view.zoom_out();
+                        scrolled += SCROLL_STEP;
+                    }
+                },
+                Event::KeyboardInput(ElementState::Pressed, _, Some(key)) => match key {
+                    //Arrow keys pan while zoomed in, otherwise left and right switch images
+                    VirtualKeyCode::Left if !view.is_zoomed_in() && imgs.len() > 1 =>
+                        next = Some((current + imgs.len() - 1) % imgs.len()),
+                    VirtualKeyCode::Right if !view.is_zoomed_in() && imgs.len() > 1 =>
+                        next = Some((current + 1) % imgs.len()),
+                    _ => handle_key(&mut view, key)
+                },
+                _ => ()
+            }
+        }
+
+        if let Some(next) = next {
+            current = next;
+            texture = load_texture(&display, &imgs[current].1);
+            view = View::new(dimensions(&imgs[current].1), display.get_framebuffer_dimensions());
+            if let Some(window) = display.get_window() {
+                window.set_title(&title(&imgs, current));
+            }
+        }
+    }
+}
+
+fn dimensions(img: &Farbfeld) -> (u32, u32) {
+    (*img.width(), *img.height())
+}
+
+fn load_texture<F: glium::backend::Facade>(display: &F, img: &Farbfeld) -> glium::texture::Texture2d {
     let mut raw_img = glium::texture::RawImage2d::from_raw_rgba_reversed(img.pixels()
                                                                              .iter()
                                                                              .flat_map(|pixel| pixel.into_iter())
                                                                              .collect::<Vec<u16>>(),
-                                                                         dimensions);
+                                                                         dimensions(img));
     raw_img.format = glium::texture::ClientFormat::U16U16U16U16; //Defaults to U8U8U8U8 which panics
-    let texture = glium::texture::Texture2d::new(&display, raw_img)
+    glium::texture::Texture2d::new(display, raw_img)
         .unwrap_or_else(|err| exit(exit_code::FAILURE,
-                                   format!("Failed to convert image for OpenGL! {}", err)));
-    let uniform = uniform!(tex: &texture);
+                                   format!("Failed to convert image for OpenGL! {}", err)))
+}
 
-    loop {
-        let mut target = display.draw();
-        target.clear_color(0.0, 0.0, 0.0, 0.0);
-        target.draw(&vertices, &indices, &program, &uniform, &Default::default())
-            .unwrap_or_else(|err| exit(exit_code::FAILURE,
-                                       format!("Failed to draw image! {}", err)));
-        target.finish().unwrap_or_else(|err| exit(exit_code::FAILURE,
-                                                  format!("Failed to draw image! {}", err)));
+fn title(imgs: &[(String, Farbfeld)], current: usize) -> String {
+    format!("{} / {} — {}", current + 1, imgs.len(), imgs[current].0)
+}
 
-        for event in display.poll_events() {
-            if let Event::Closed = event {
-                return
-            }
-        }
+fn handle_key(view: &mut View, key: VirtualKeyCode) {
+    match key {
+        VirtualKeyCode::Add | VirtualKeyCode::Equals => view.zoom_in(),
+        VirtualKeyCode::Subtract | VirtualKeyCode::Minus => view.zoom_out(),
+        VirtualKeyCode::Left => view.pan(-1, 0),
+        VirtualKeyCode::Right => view.pan(1, 0),
+        VirtualKeyCode::Up => view.pan(0, 1),
+        VirtualKeyCode::Down => view.pan(0, -1),
+        VirtualKeyCode::Home => view.reset(),
+        _ => ()
     }
 }
 
 fn exit<T: AsRef<str>>(code: i32, msg: T) -> ! {
+    print_err(msg);
+    std::process::exit(code)
+}
+
+fn print_err<T: AsRef<str>>(msg: T) {
     use std::io::Write;
     if writeln!(std::io::stderr(), "{}", msg.as_ref()).is_err() {
         println!("Failed to write to stderr! {}", msg.as_ref());
     }
-    std::process::exit(code)
 }
 
-fn load_img() -> Farbfeld {
-    if let Some(path) = env::args().nth(1) {
-        Farbfeld::from_file(path)
-            .unwrap_or_else(|err| handle_load_err("Failed to load from file! ", &err))
-    } else {
+/// Loads every image given on the command line, paired with its file name. Files which fail to
+/// load are reported and skipped. Reads a single image from stdin if no paths are given.
+fn load_imgs() -> Vec<(String, Farbfeld)> {
+    let paths = env::args().skip(1).collect::<Vec<String>>();
+    if paths.is_empty() {
         let stdin = io::stdin();
         let handle = stdin.lock();
-        Farbfeld::from_read(handle)
-            .unwrap_or_else(|err| handle_load_err("Failed to read image from stdin! ", &err))
+        let img = Farbfeld::from_read(handle)
+            .unwrap_or_else(|err| handle_load_err("Failed to read image from stdin! ", &err));
+        return vec![("stdin".to_owned(), img)]
+    }
+
+    let mut code = exit_code::FAILURE;
+    let imgs = paths.iter()
+        .filter_map(|path| match Farbfeld::from_file(path.as_str()) {
+            Ok(img) => Some((file_name(path), img)),
+            Err(err) => {
+                let (err_code, msg) = load_err(string_build!("Failed to load ", path, "! "), &err);
+                code = err_code;
+                print_err(msg);
+                None
+            }
+        })
+        .collect::<Vec<(String, Farbfeld)>>();
+    if imgs.is_empty() {
+        exit(code, "No images could be loaded!")
     }
+    imgs
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path).file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_owned())
 }
 
 fn handle_load_err<T: AsRef<str>>(start: T, err: &rufferr::Error) -> ! {
+    let (code, msg) = load_err(start, err);
+    exit(code, msg)
+}
+
+fn load_err<T: AsRef<str>>(start: T, err: &rufferr::Error) -> (i32, String) {
     match *err.kind() {
         rufferr::ErrorKind::IoError(ref e) =>
-            exit(exit_code::IO_ERROR, string_build!(start.as_ref(), &e.to_string())),
-        rufferr::ErrorKind::InvalidFarbfeldDimensions =>
-            exit(exit_code::DATA_ERROR, string_build!(start.as_ref(), &err.to_string())),
+            (exit_code::IO_ERROR, string_build!(start.as_ref(), &e.to_string())),
         rufferr::ErrorKind::NomError(ref e) =>
-            exit(exit_code::DATA_ERROR, string_build!(start.as_ref(), &e.to_string())),
-        _ => unreachable!()
+            (exit_code::DATA_ERROR, string_build!(start.as_ref(), &e.to_string())),
+        _ => (exit_code::DATA_ERROR, string_build!(start.as_ref(), &err.to_string()))
     }
 }
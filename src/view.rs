@@ -0,0 +1,93 @@
+const ZOOM_STEP: f32 = 1.25;
+const MAX_ZOOM: i32 = 20;
+const PAN_STEP: f32 = 0.1;
+
+/// Zoom and pan state for the viewer, turned into a vertex transform by `matrix`. Zoom is kept as
+/// whole steps and the pan offset is in image space, so zooming stays centred on the same part of
+/// the image.
+pub struct View {
+    zoom: i32,
+    offset: [f32; 2],
+    img_dims: (u32, u32),
+    win_dims: (u32, u32)
+}
+
+impl View {
+    pub fn new(img_dims: (u32, u32), win_dims: (u32, u32)) -> View {
+        View {
+            zoom: 0,
+            offset: [0.0, 0.0],
+            img_dims,
+            win_dims
+        }
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.zoom = (self.zoom + 1).min(MAX_ZOOM);
+    }
+
+    /// Zooms out, recentring the image once it no longer fills more than the window.
+    pub fn zoom_out(&mut self) {
+        self.zoom = (self.zoom - 1).max(-MAX_ZOOM);
+        if !self.is_zoomed_in() {
+            self.offset = [0.0, 0.0];
+        }
+    }
+
+    /// Moves the image by the given number of pan steps, in screen directions. A step covers the
+    /// same distance on screen at every zoom. The offset is clamped to the image's own extent so
+    /// part of it always stays under the centre of the window. Does nothing unless zoomed in.
+    pub fn pan(&mut self, x: i32, y: i32) {
+        if !self.is_zoomed_in() {
+            return
+        }
+        let step = PAN_STEP / self.zoom_factor();
+        self.offset[0] = clamp_offset(self.offset[0] + x as f32 * step);
+        self.offset[1] = clamp_offset(self.offset[1] + y as f32 * step);
+    }
+
+    /// Returns the view to fit the window with the image centred.
+    pub fn reset(&mut self) {
+        self.zoom = 0;
+        self.offset = [0.0, 0.0];
+    }
+
+    /// Whether the view is zoomed in past fitting the window, which is the only time it can pan.
+    pub fn is_zoomed_in(&self) -> bool {
+        self.zoom > 0
+    }
+
+    pub fn resize(&mut self, win_dims: (u32, u32)) {
+        self.win_dims = win_dims;
+    }
+
+    /// Column-major transform for the vertex shader, translating by the pan offset and then
+    /// scaling. The quad is scaled down on one axis so the image keeps its aspect ratio whatever
+    /// the window shape.
+    pub fn matrix(&self) -> [[f32; 4]; 4] {
+        let img_ratio = self.img_dims.0.max(1) as f32 / self.img_dims.1.max(1) as f32;
+        let win_ratio = self.win_dims.0.max(1) as f32 / self.win_dims.1.max(1) as f32;
+        let (fit_x, fit_y) = if win_ratio > img_ratio {
+            (img_ratio / win_ratio, 1.0)
+        } else {
+            (1.0, win_ratio / img_ratio)
+        };
+        let scale_x = fit_x * self.zoom_factor();
+        let scale_y = fit_y * self.zoom_factor();
+
+        [[scale_x, 0.0, 0.0, 0.0],
+         [0.0, scale_y, 0.0, 0.0],
+         [0.0, 0.0, 1.0, 0.0],
+         [scale_x * self.offset[0], scale_y * self.offset[1], 0.0, 1.0]]
+    }
+
+    fn zoom_factor(&self) -> f32 {
+        ZOOM_STEP.powi(self.zoom)
+    }
+}
+
+/// Limits an image space offset to the quad, which spans -1 to 1 on each axis.
+fn clamp_offset(offset: f32) -> f32 {
+    let offset = offset.max(-1.0);
+    offset.min(1.0)
+}